@@ -0,0 +1,206 @@
+use std::{collections::HashMap, error::Error, sync::Arc};
+
+use async_trait::async_trait;
+use twilight_http::Client as DiscordClient;
+use twilight_model::gateway::payload::incoming::MessageCreate;
+
+use crate::{transforms, AppData};
+
+pub type CommandError = Box<dyn Error + Send + Sync>;
+
+/// Everything a `Command` needs to do its job, bundled so new commands don't
+/// have to grow the `execute` signature.
+pub struct CommandContext {
+    pub http: Arc<DiscordClient>,
+    pub message: Arc<Box<MessageCreate>>,
+    pub app_data: Arc<AppData>,
+}
+
+/// What a command wants to happen after it runs.
+pub enum CommandOutcome {
+    /// Rewrite the outgoing GPT message before it's sent (e.g. persona
+    /// prompts like `!expert`/`!jb`/`!uwu`).
+    InjectPrompt(String),
+    /// Reply to the triggering message directly, skipping GPT entirely.
+    Reply(String),
+    /// The command already did everything it needed to (sent its own
+    /// messages, etc); nothing left for the caller to do.
+    None,
+}
+
+/// A chat command, keyed in the `CommandRegistry` by the prefix token that
+/// triggers it (e.g. `"!ping"`).
+#[async_trait]
+pub trait Command: Send + Sync {
+    async fn execute(&self, ctx: &CommandContext, args: &str) -> Result<CommandOutcome, CommandError>;
+}
+
+#[derive(Default)]
+pub struct CommandRegistry {
+    commands: HashMap<String, Box<dyn Command>>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(&mut self, token: &str, command: Box<dyn Command>) {
+        self.commands.insert(token.to_string(), command);
+    }
+
+    pub fn get(&self, token: &str) -> Option<&dyn Command> {
+        self.commands.get(token).map(AsRef::as_ref)
+    }
+}
+
+/// Splits the leading whitespace-delimited word off of `input`, returning
+/// `(command, rest)`. `rest` has its leading whitespace trimmed.
+pub fn parse_command(input: &str) -> (&str, &str) {
+    match input.trim_start().split_once(char::is_whitespace) {
+        Some((command, rest)) => (command, rest.trim_start()),
+        None => (input.trim(), ""),
+    }
+}
+
+/// Builds the default registry of commands this bot ships with. These are
+/// only meaningful when the message is addressed to the bot (mention/reply),
+/// since they rewrite or short-circuit the outgoing GPT turn.
+pub fn default_registry() -> CommandRegistry {
+    let mut registry = CommandRegistry::new();
+
+    registry.register("!expert", Box::new(PromptCommand { prompt_id: "expert" }));
+    registry.register("!jb", Box::new(PromptCommand { prompt_id: "jb" }));
+    registry.register("!uwu", Box::new(UwuCommand));
+    registry.register("!ping", Box::new(PingCommand));
+    registry.register("!owo", Box::new(OwoifyCommand));
+    registry.register("!leet", Box::new(LeetspeakCommand));
+    registry.register("!mock", Box::new(MockCaseCommand));
+
+    registry
+}
+
+/// Builds the registry of commands that are safe to dispatch from *any*
+/// message, not just ones addressed to the bot via mention/reply. These
+/// never inject a persona prompt or otherwise assume they're talking to the
+/// bot on the user's behalf — they just reply or do nothing.
+pub fn default_standalone_registry() -> CommandRegistry {
+    let mut registry = CommandRegistry::new();
+
+    registry.register("!ping", Box::new(PingCommand));
+    registry.register("!owo", Box::new(OwoifyCommand));
+    registry.register("!leet", Box::new(LeetspeakCommand));
+    registry.register("!mock", Box::new(MockCaseCommand));
+
+    registry
+}
+
+/// Rewrites the outgoing GPT message with a persona prompt looked up from
+/// `AppData::all_prompts`, followed by whatever the user typed after the
+/// command. Backs `!expert` and `!jb`.
+struct PromptCommand {
+    prompt_id: &'static str,
+}
+
+#[async_trait]
+impl Command for PromptCommand {
+    async fn execute(&self, ctx: &CommandContext, args: &str) -> Result<CommandOutcome, CommandError> {
+        let prompt = ctx
+            .app_data
+            .all_prompts
+            .iter()
+            .find(|prompt| prompt.prompt_id == self.prompt_id)
+            .ok_or(format!("No {} prompt", self.prompt_id))?;
+
+        Ok(CommandOutcome::InjectPrompt(
+            format!("{} {}", prompt.prompt, args).trim().to_string(),
+        ))
+    }
+}
+
+/// Stylizes the GPT persona prompt for a given name, e.g. `!uwu "Jane Doe"`.
+struct UwuCommand;
+
+#[async_trait]
+impl Command for UwuCommand {
+    async fn execute(&self, ctx: &CommandContext, args: &str) -> Result<CommandOutcome, CommandError> {
+        if !args.contains('"') {
+            let bot_resp = ctx
+                .http
+                .create_message(ctx.message.channel_id)
+                .content("You need to provide a name.")?
+                .reply(ctx.message.id)
+                .await?;
+
+            let http = Arc::clone(&ctx.http);
+            let channel_id = ctx.message.channel_id;
+
+            tokio::spawn(async move {
+                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+
+                http.delete_message(channel_id, bot_resp.model().await.unwrap().id)
+                    .await
+                    .unwrap();
+            });
+
+            return Ok(CommandOutcome::None);
+        }
+
+        let uwu_prompt = ctx
+            .app_data
+            .all_prompts
+            .iter()
+            .find(|prompt| prompt.prompt_id == "uwu")
+            .ok_or("No uwu prompt")?;
+
+        let name = args.replace('"', "");
+
+        let prompt = uwu_prompt
+            .prompt
+            .replace("{FIRST_NAME}", "{NAME}")
+            .replace("{FULL_NAME}", "{NAME}")
+            .replace("{LAST_NAME}", "{NAME}")
+            .replace("{NAME}", name.trim());
+
+        Ok(CommandOutcome::InjectPrompt(prompt))
+    }
+}
+
+/// Liveness check, independent of the GPT pipeline.
+struct PingCommand;
+
+#[async_trait]
+impl Command for PingCommand {
+    async fn execute(&self, _ctx: &CommandContext, _args: &str) -> Result<CommandOutcome, CommandError> {
+        Ok(CommandOutcome::Reply("Pong!".to_string()))
+    }
+}
+
+/// Local text transforms. These never touch OpenAI or MongoDB: the command's
+/// own text is rewritten in-process and replied directly.
+struct OwoifyCommand;
+
+#[async_trait]
+impl Command for OwoifyCommand {
+    async fn execute(&self, _ctx: &CommandContext, args: &str) -> Result<CommandOutcome, CommandError> {
+        Ok(CommandOutcome::Reply(transforms::owoify(args)))
+    }
+}
+
+struct LeetspeakCommand;
+
+#[async_trait]
+impl Command for LeetspeakCommand {
+    async fn execute(&self, _ctx: &CommandContext, args: &str) -> Result<CommandOutcome, CommandError> {
+        Ok(CommandOutcome::Reply(transforms::leetspeak(args)))
+    }
+}
+
+struct MockCaseCommand;
+
+#[async_trait]
+impl Command for MockCaseCommand {
+    async fn execute(&self, _ctx: &CommandContext, args: &str) -> Result<CommandOutcome, CommandError> {
+        Ok(CommandOutcome::Reply(transforms::mock_case(args)))
+    }
+}
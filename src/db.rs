@@ -0,0 +1,17 @@
+use std::error::Error;
+
+use mongodb::options::ClientOptions;
+
+/// Opens a fresh connection to the `MONGO_URL` database, matching the
+/// existing per-call connection pattern used throughout the bot.
+pub async fn get_mongo_client() -> Result<mongodb::Client, Box<dyn Error + Send + Sync>> {
+    let mongo_url = std::env::var("MONGO_URL")?;
+
+    let mut client_options = ClientOptions::parse(&mongo_url).await?;
+
+    client_options.app_name = Some("kirogpt".to_string());
+
+    Ok::<mongodb::Client, Box<dyn Error + Send + Sync>>(mongodb::Client::with_options(
+        client_options,
+    )?)
+}
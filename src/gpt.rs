@@ -0,0 +1,84 @@
+use std::error::Error;
+
+use futures_util::Stream;
+
+use crate::{ChatCompletionRequest, ChatCompletionResponse, Message};
+
+pub type GptError = Box<dyn Error + Send + Sync>;
+
+const OPENAI_CHAT_URL: &str = "https://api.openai.com/v1/chat/completions";
+const MODEL: &str = "gpt-3.5-turbo";
+
+/// The model this bot is configured to talk to, surfaced for e.g. `GET
+/// /v1/models`.
+pub fn model_name() -> &'static str {
+    MODEL
+}
+
+fn build_client() -> Result<(reqwest::Client, reqwest::header::HeaderMap), GptError> {
+    let gpt_token = std::env::var("GPT_TOKEN")?;
+
+    let mut headers = reqwest::header::HeaderMap::new();
+
+    headers.insert(
+        reqwest::header::AUTHORIZATION,
+        reqwest::header::HeaderValue::from_str(&format!("Bearer {}", gpt_token))?,
+    );
+
+    headers.insert(
+        reqwest::header::CONTENT_TYPE,
+        reqwest::header::HeaderValue::from_static("application/json"),
+    );
+
+    Ok((reqwest::Client::new(), headers))
+}
+
+/// Runs `messages` through the chat-completions endpoint and waits for the
+/// full response. Shared by the Discord handler and the `/v1/chat/completions`
+/// HTTP endpoint.
+pub async fn complete(mut messages: Vec<Message>) -> Result<ChatCompletionResponse, GptError> {
+    let prompt_tokens = crate::tokens::trim_to_budget(MODEL, &mut messages);
+
+    let (client, headers) = build_client()?;
+
+    let res = client
+        .post(OPENAI_CHAT_URL)
+        .headers(headers)
+        .json(&ChatCompletionRequest {
+            model: MODEL.to_string(),
+            messages,
+            stream: false,
+        })
+        .send()
+        .await?;
+
+    let mut response: ChatCompletionResponse = res.json().await?;
+
+    response.usage.prompt_tokens = prompt_tokens as i64;
+
+    Ok(response)
+}
+
+/// Same request as `complete`, but with `stream: true` set and the raw SSE
+/// byte stream handed back so the caller can forward `data: ` frames as they
+/// arrive instead of waiting for the whole completion.
+pub async fn complete_stream(
+    mut messages: Vec<Message>,
+) -> Result<impl Stream<Item = reqwest::Result<bytes::Bytes>>, GptError> {
+    crate::tokens::trim_to_budget(MODEL, &mut messages);
+
+    let (client, headers) = build_client()?;
+
+    let res = client
+        .post(OPENAI_CHAT_URL)
+        .headers(headers)
+        .json(&ChatCompletionRequest {
+            model: MODEL.to_string(),
+            messages,
+            stream: true,
+        })
+        .send()
+        .await?;
+
+    Ok(res.bytes_stream())
+}
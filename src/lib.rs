@@ -1,7 +1,14 @@
-use std::sync::{Arc, Mutex};
-
 use serde::{Deserialize, Serialize};
 
+pub mod commands;
+pub mod db;
+pub mod gpt;
+pub mod server;
+pub mod tokens;
+pub mod transforms;
+
+use commands::CommandRegistry;
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ChatCompletionResponse {
@@ -17,6 +24,8 @@ pub struct ChatCompletionResponse {
 pub struct ChatCompletionRequest {
     pub model: String,
     pub messages: Vec<Message>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stream: bool,
 }
 
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -48,6 +57,36 @@ pub struct Message {
     pub content: String,
 }
 
+/// A single streamed chunk from a `stream: true` completion request.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChatCompletionChunk {
+    pub id: String,
+    pub object: String,
+    pub created: i64,
+    pub choices: Vec<ChunkChoice>,
+}
+
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunkChoice {
+    pub index: i64,
+    pub delta: Delta,
+    #[serde(rename = "finish_reason")]
+    pub finish_reason: Option<String>,
+}
+
+/// Mirrors `Message`, but every field is optional since a delta may only
+/// carry a `role` on the first frame and `content` on the rest.
+#[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delta {
+    #[serde(default)]
+    pub role: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
 #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Usage {
@@ -59,9 +98,16 @@ pub struct Usage {
     pub total_tokens: i64,
 }
 
-#[derive(Default, Debug, Clone, PartialEq)]
+#[derive(Default)]
 pub struct AppData {
     pub username: String,
     pub bot_id: u64,
     pub all_prompts: Vec<PromptDocument>,
+    /// Full command set, including persona-prompt commands (`!expert`/`!jb`/
+    /// `!uwu`). Only dispatched for messages addressed to the bot.
+    pub commands: CommandRegistry,
+    /// Subset of `commands` that's safe to dispatch from any message, not
+    /// just ones addressed to the bot (no persona injection, no assuming
+    /// the user is talking to the bot).
+    pub standalone_commands: CommandRegistry,
 }
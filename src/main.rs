@@ -5,8 +5,8 @@ use std::{
 
 use dotenv::dotenv;
 use futures_util::{StreamExt, TryStreamExt};
-use kirogpt::{AppData, ChatCompletionRequest, ChatDocument, Message, PromptDocument};
-use mongodb::{bson, options::ClientOptions};
+use kirogpt::{db::get_mongo_client, AppData, ChatDocument, PromptDocument};
+use mongodb::bson;
 use tokio::{main, sync::Mutex};
 use twilight_cache_inmemory::{InMemoryCache, ResourceType};
 use twilight_gateway::{Event, Intents, Shard, ShardId};
@@ -62,6 +62,14 @@ async fn main() -> Result<(), Box<dyn Error + Send + Sync>> {
         username,
         bot_id,
         all_prompts,
+        commands: kirogpt::commands::default_registry(),
+        standalone_commands: kirogpt::commands::default_standalone_registry(),
+    });
+
+    tokio::spawn(async move {
+        if let Err(error) = kirogpt::server::run().await {
+            eprintln!("HTTP API error: {}", error);
+        }
     });
 
     loop {
@@ -206,10 +214,26 @@ async fn handle_event(
                 return Ok(());
             }
 
-            if message.content == "!ping" {
-                http.create_message(message.channel_id)
-                    .content("Pong!")?
-                    .await?;
+            // Not addressed to the bot: only dispatch standalone commands
+            // (ping/owo/leet/mock), never persona-prompt commands like
+            // `!uwu`, which assume they're rewriting a turn aimed at the bot
+            // and may otherwise reply publicly to unrelated chatter.
+            let (command_token, args) = kirogpt::commands::parse_command(&message.content);
+
+            if let Some(command) = app_data.standalone_commands.get(command_token) {
+                let ctx = kirogpt::commands::CommandContext {
+                    http: Arc::clone(&http),
+                    message: Arc::clone(&message),
+                    app_data: Arc::clone(&app_data),
+                };
+
+                if let kirogpt::commands::CommandOutcome::Reply(text) =
+                    command.execute(&ctx, args).await?
+                {
+                    http.create_message(message.channel_id)
+                        .content(&text)?
+                        .await?;
+                }
             }
         }
         _ => {}
@@ -218,18 +242,6 @@ async fn handle_event(
     Ok(())
 }
 
-async fn get_mongo_client() -> Result<mongodb::Client, Box<dyn Error + Send + Sync>> {
-    let mongo_url = std::env::var("MONGO_URL")?;
-
-    let mut client_options = ClientOptions::parse(&mongo_url).await?;
-
-    client_options.app_name = Some("kirogpt".to_string());
-
-    Ok::<mongodb::Client, Box<dyn Error + Send + Sync>>(mongodb::Client::with_options(
-        client_options,
-    )?)
-}
-
 async fn handle_message(
     http: Arc<DiscordClient>,
     message: Arc<Box<MessageCreate>>,
@@ -238,26 +250,6 @@ async fn handle_message(
     history: Option<Vec<ChatDocument>>,
     reply_id: Option<String>,
 ) -> Result<(), Box<dyn Error + Send + Sync>> {
-    let client = reqwest::Client::new();
-
-    let mut headers = reqwest::header::HeaderMap::new();
-
-    // Authorization header
-
-    let gpt_token = std::env::var("GPT_TOKEN")?;
-
-    headers.insert(
-        reqwest::header::AUTHORIZATION,
-        reqwest::header::HeaderValue::from_str(&format!("Bearer {}", gpt_token))?,
-    );
-
-    // Content-Type header
-
-    headers.insert(
-        reqwest::header::CONTENT_TYPE,
-        reqwest::header::HeaderValue::from_static("application/json"),
-    );
-
     let mut messages = vec![];
 
     if history.is_some() {
@@ -274,78 +266,30 @@ async fn handle_message(
 
     user_message = user_message.replace(", ", "");
 
-    let expert_prompt = app_data
-        .all_prompts
-        .iter()
-        .find(|prompt| prompt.prompt_id == "expert")
-        .ok_or("No expert prompt")?;
-
-    let jb_prompt = app_data
-        .all_prompts
-        .iter()
-        .find(|prompt| prompt.prompt_id == "jb")
-        .ok_or("No jb prompt")?;
+    let (command_token, args) = kirogpt::commands::parse_command(&user_message);
 
-    let uwu_prompt = app_data
-        .all_prompts
-        .iter()
-        .find(|prompt| prompt.prompt_id == "uwu")
-        .ok_or("No uwu prompt")?;
-
-    user_message = user_message.replace("!expert", expert_prompt.prompt.as_str());
-
-    user_message = user_message.replace("!jb", jb_prompt.prompt.as_str());
-
-    if user_message.contains("!uwu") {
-        let arguments = user_message.split_whitespace().collect::<Vec<_>>();
-
-        let last_argument = arguments.last().ok_or("No last argument")?;
-
-        if last_argument.contains("\"") {
-            // if last_argument.split_whitespace().count() <= 1 {
-            //     http.create_message(message.channel_id)
-            //         .content("You need to provide a name.")?
-            //         .reply(message.id)
-            //         .await?;
-
-            //     return Ok(());
-            // }
-
-            // Trim the \" if it exists
-            let last_argument = last_argument.replace("\"", "");
-
-            let name = last_argument
-                .split_whitespace()
-                .collect::<Vec<_>>()
-                .join(" ");
-
-            // Replace {FIRST_NAME} with the first name, and {LAST_NAME} with the last name
-
-            let uwu_prompt = uwu_prompt.prompt.replace("{FIRST_NAME}", "{NAME}");
-
-            let uwu_prompt = uwu_prompt.replace("{FULL_NAME}", "{NAME}");
-
-            let uwu_prompt = uwu_prompt.replace("{LAST_NAME}", "{NAME}");
-
-            let uwu_prompt = uwu_prompt.replace("{NAME}", &name);
-
-            user_message = user_message.replace("!uwu", &uwu_prompt);
-        } else {
-            let bot_resp = http
-                .create_message(message.channel_id)
-                .content("You need to provide a name.")?
-                .reply(message.id)
-                .await?;
-
-            tokio::spawn(async move {
-                tokio::time::sleep(tokio::time::Duration::from_secs(5)).await;
+    if let Some(command) = app_data.commands.get(command_token) {
+        let ctx = kirogpt::commands::CommandContext {
+            http: Arc::clone(&http),
+            message: Arc::clone(&message),
+            app_data: Arc::clone(&app_data),
+        };
 
-                http.delete_message(message.channel_id, bot_resp.model().await.unwrap().id)
-                    .await
-                    .unwrap();
-            });
+        match command.execute(&ctx, args).await? {
+            kirogpt::commands::CommandOutcome::InjectPrompt(prompt) => {
+                user_message = prompt;
+            }
+            kirogpt::commands::CommandOutcome::Reply(text) => {
+                http.create_message(message.channel_id)
+                    .content(&text)?
+                    .reply(message.id)
+                    .await?;
 
-            return Ok(());
+                return Ok(());
+            }
+            kirogpt::commands::CommandOutcome::None => {
+                return Ok(());
+            }
         }
     }
 
@@ -360,7 +304,7 @@ async fn handle_message(
 
     processing.lock().await.push(u64::from(message.id));
 
-    tokio::spawn({
+    let typing_task = tokio::spawn({
         let finished = Arc::clone(&finished);
         let http = Arc::clone(&http);
         let message = Arc::clone(&message);
@@ -375,42 +319,92 @@ async fn handle_message(
         }
     });
 
-    let res = client
-        .post("https://api.openai.com/v1/chat/completions")
-        .headers(headers)
-        .json(&kirogpt::ChatCompletionRequest {
-            model: "gpt-3.5-turbo".to_string(),
-            messages: messages.clone(),
-        })
-        .send()
-        .await?;
+    // Aborts the typing-indicator task when this function returns, including
+    // via an early `?` on a fallible streaming step — otherwise a mid-stream
+    // error would leave it looping `create_typing_trigger` forever, since it
+    // only ever checks `finished`.
+    let _typing_guard = TypingTaskGuard(typing_task);
 
-    finished.store(true, std::sync::atomic::Ordering::SeqCst);
+    let mut byte_stream = kirogpt::gpt::complete_stream(messages.clone()).await?;
 
     processing
         .lock()
         .await
         .retain(|id| *id != u64::from(message.id));
 
-    println!("Response: {:?}", res);
+    // Raw bytes, not `String`: network chunks don't align to UTF-8 character
+    // boundaries, so decoding each chunk independently would mangle any
+    // multi-byte character split across two chunks. Decode once a full
+    // `\n\n`-delimited frame has been carved out instead.
+    let mut frame_buf: Vec<u8> = Vec::new();
+    let mut response = String::new();
+    let mut discord_msg_id = None;
+    let mut last_edit = tokio::time::Instant::now();
+    const EDIT_INTERVAL: tokio::time::Duration = tokio::time::Duration::from_millis(750);
 
-    let response: kirogpt::ChatCompletionResponse = res.json().await?;
+    'frames: while let Some(bytes) = byte_stream.next().await {
+        frame_buf.extend_from_slice(&bytes?);
 
-    let mut response = response.choices;
+        while let Some(frame_end) = find_double_newline(&frame_buf) {
+            let frame = String::from_utf8(frame_buf.drain(..frame_end + 2).collect())?;
 
-    let response = response.pop().ok_or("No response")?;
+            let Some(data) = frame.trim().strip_prefix("data: ") else {
+                continue;
+            };
 
-    let response = response.message.content;
+            if data == "[DONE]" {
+                break 'frames;
+            }
 
-    println!("Response: {}", response);
+            let chunk: kirogpt::ChatCompletionChunk = serde_json::from_str(data)?;
+
+            let Some(delta) = chunk.choices.first().and_then(|choice| choice.delta.content.clone())
+            else {
+                continue;
+            };
+
+            // The first delta means the model has started responding, so the
+            // typing indicator has done its job.
+            finished.store(true, std::sync::atomic::Ordering::SeqCst);
+
+            response.push_str(&delta);
 
-    let bot_msg = http
-        .create_message(message.channel_id)
-        .content(&response)?
-        .reply(message.id)
+            match discord_msg_id {
+                None => {
+                    let bot_msg = http
+                        .create_message(message.channel_id)
+                        .content(&response)?
+                        .reply(message.id)
+                        .await?;
+
+                    discord_msg_id = Some(bot_msg.model().await?.id);
+                    last_edit = tokio::time::Instant::now();
+                }
+                Some(id) if last_edit.elapsed() >= EDIT_INTERVAL => {
+                    http.update_message(message.channel_id, id)
+                        .content(Some(&response))?
+                        .await?;
+
+                    last_edit = tokio::time::Instant::now();
+                }
+                Some(_) => {}
+            }
+        }
+    }
+
+    finished.store(true, std::sync::atomic::Ordering::SeqCst);
+
+    let discord_msg_id = discord_msg_id.ok_or("No response")?;
+
+    // Final edit to make sure the last few characters (younger than the
+    // throttle window) actually make it to Discord.
+    http.update_message(message.channel_id, discord_msg_id)
+        .content(Some(&response))?
         .await?;
 
-    let bot_msg_id = u64::from(bot_msg.model().await?.id);
+    let bot_msg_id = u64::from(discord_msg_id);
+
+    println!("Response: {}", response);
 
     messages.push(kirogpt::Message {
         role: "assistant".to_string(),
@@ -463,3 +457,19 @@ async fn handle_message(
 
     Ok(())
 }
+
+/// Aborts the wrapped task when dropped, so the typing-indicator loop can't
+/// outlive the request it was started for, regardless of which `return` or
+/// `?` ends up leaving the function.
+struct TypingTaskGuard(tokio::task::JoinHandle<()>);
+
+impl Drop for TypingTaskGuard {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Returns the byte offset of the first `\n\n` in `buf`, if any.
+fn find_double_newline(buf: &[u8]) -> Option<usize> {
+    buf.windows(2).position(|window| window == b"\n\n")
+}
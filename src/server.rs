@@ -0,0 +1,144 @@
+use std::{convert::Infallible, error::Error, net::SocketAddr, sync::Arc};
+
+use hyper::{
+    header::AUTHORIZATION,
+    service::{make_service_fn, service_fn},
+    Body, Method, Request, Response, Server, StatusCode,
+};
+use mongodb::bson;
+
+use crate::{db, gpt, ChatCompletionRequest, ChatDocument};
+
+/// Serves the OpenAI-compatible HTTP API alongside the Discord gateway loop,
+/// so other clients can talk to the same GPT pipeline without going through
+/// Discord.
+pub async fn run() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let addr: SocketAddr = std::env::var("HTTP_ADDR")
+        .unwrap_or_else(|_| "0.0.0.0:8080".to_string())
+        .parse()?;
+
+    // Shared secret clients must present as `Authorization: Bearer <key>`.
+    // This endpoint spends OpenAI credits and serves stored Discord
+    // conversations by id, so it can't be left open the way `/v1/models` is.
+    let api_key = Arc::new(std::env::var("HTTP_API_KEY")?);
+
+    let make_svc = make_service_fn(move |_conn| {
+        let api_key = Arc::clone(&api_key);
+        async move { Ok::<_, Infallible>(service_fn(move |req| handle(req, Arc::clone(&api_key)))) }
+    });
+
+    println!("HTTP API listening on {}", addr);
+
+    Server::bind(&addr).serve(make_svc).await?;
+
+    Ok(())
+}
+
+async fn handle(req: Request<Body>, api_key: Arc<String>) -> Result<Response<Body>, Infallible> {
+    Ok(route(req, &api_key).await.unwrap_or_else(|error| {
+        eprintln!("HTTP API error: {}", error);
+
+        Response::builder()
+            .status(StatusCode::INTERNAL_SERVER_ERROR)
+            .body(Body::from(error.to_string()))
+            .expect("building an error response should never fail")
+    }))
+}
+
+async fn route(req: Request<Body>, api_key: &str) -> Result<Response<Body>, Box<dyn Error + Send + Sync>> {
+    let path = req.uri().path().to_string();
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (req.method(), segments.as_slice()) {
+        (&Method::POST, ["v1", "chat", "completions"]) => {
+            if !authorized(&req, api_key) {
+                return unauthorized();
+            }
+
+            chat_completions(req).await
+        }
+        (&Method::GET, ["v1", "models"]) => models(),
+        (&Method::GET, ["conversations", id]) => {
+            if !authorized(&req, api_key) {
+                return unauthorized();
+            }
+
+            conversation(id).await
+        }
+        _ => not_found(),
+    }
+}
+
+/// Checks the `Authorization: Bearer <key>` header against the configured
+/// `HTTP_API_KEY`. Guards the routes that spend OpenAI credits or read
+/// stored conversations; `/v1/models` stays open since it's just config.
+fn authorized(req: &Request<Body>, api_key: &str) -> bool {
+    req.headers()
+        .get(AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| token == api_key)
+}
+
+fn unauthorized() -> Result<Response<Body>, Box<dyn Error + Send + Sync>> {
+    Ok(Response::builder()
+        .status(StatusCode::UNAUTHORIZED)
+        .body(Body::from("Unauthorized"))?)
+}
+
+async fn chat_completions(req: Request<Body>) -> Result<Response<Body>, Box<dyn Error + Send + Sync>> {
+    let body = hyper::body::to_bytes(req.into_body()).await?;
+
+    let request: ChatCompletionRequest = serde_json::from_slice(&body)?;
+
+    if request.stream {
+        let stream = gpt::complete_stream(request.messages).await?;
+
+        return Ok(Response::builder()
+            .header("Content-Type", "text/event-stream")
+            .body(Body::wrap_stream(stream))?);
+    }
+
+    let response = gpt::complete(request.messages).await?;
+
+    Ok(Response::builder()
+        .header("Content-Type", "application/json")
+        .body(Body::from(serde_json::to_vec(&response)?))?)
+}
+
+fn models() -> Result<Response<Body>, Box<dyn Error + Send + Sync>> {
+    let body = serde_json::json!({
+        "object": "list",
+        "data": [{
+            "id": gpt::model_name(),
+            "object": "model",
+        }],
+    });
+
+    Ok(Response::builder()
+        .header("Content-Type", "application/json")
+        .body(Body::from(serde_json::to_vec(&body)?))?)
+}
+
+async fn conversation(id: &str) -> Result<Response<Body>, Box<dyn Error + Send + Sync>> {
+    let mongo_client = db::get_mongo_client().await?;
+
+    let db = mongo_client.database("kirogpt");
+
+    let collection = db.collection::<ChatDocument>("messages");
+
+    let doc = collection.find_one(bson::doc! { "id": id }, None).await?;
+
+    match doc {
+        Some(doc) => Ok(Response::builder()
+            .header("Content-Type", "application/json")
+            .body(Body::from(serde_json::to_vec(&doc)?))?),
+        None => not_found(),
+    }
+}
+
+fn not_found() -> Result<Response<Body>, Box<dyn Error + Send + Sync>> {
+    Ok(Response::builder()
+        .status(StatusCode::NOT_FOUND)
+        .body(Body::from("Not found"))?)
+}
@@ -0,0 +1,71 @@
+use crate::Message;
+
+/// Tokens ChatML adds around every `role`/`content` pair, on top of the
+/// tokens the content itself encodes to.
+const TOKENS_PER_MESSAGE: usize = 3;
+
+/// Tokens ChatML adds once per request to prime the assistant's reply.
+const PRIMING_TOKENS: usize = 3;
+
+/// Tokens reserved for the model's completion, subtracted from the context
+/// window when computing how much room the prompt has to work with.
+const COMPLETION_MARGIN: usize = 512;
+
+/// The context window, in tokens, for a given model. Unrecognized models
+/// get a conservative default rather than an error, since trimming too much
+/// is far safer than not trimming at all.
+fn context_window(model: &str) -> usize {
+    match model {
+        "gpt-3.5-turbo" | "gpt-3.5-turbo-0613" => 4096,
+        "gpt-3.5-turbo-16k" => 16384,
+        "gpt-4" | "gpt-4-0613" => 8192,
+        "gpt-4-32k" => 32768,
+        _ => 4096,
+    }
+}
+
+/// Counts the tokens `text` would encode to for `model`. Uses `tiktoken-rs`'s
+/// BPE for models it knows about, falling back to a chars/4 approximation
+/// for anything else.
+fn count_tokens(model: &str, text: &str) -> usize {
+    match tiktoken_rs::get_bpe_from_model(model) {
+        Ok(bpe) => bpe.encode_with_special_tokens(text).len(),
+        Err(_) => text.chars().count().div_ceil(4),
+    }
+}
+
+/// Estimates the total prompt tokens `messages` would cost, including the
+/// per-message ChatML overhead and the priming tokens added once per
+/// request.
+pub fn estimate_tokens(model: &str, messages: &[Message]) -> usize {
+    let body_tokens: usize = messages
+        .iter()
+        .map(|message| {
+            count_tokens(model, &message.role) + count_tokens(model, &message.content) + TOKENS_PER_MESSAGE
+        })
+        .sum();
+
+    body_tokens + PRIMING_TOKENS
+}
+
+/// Drops the oldest non-system turns from `messages` until the estimated
+/// prompt token count fits under `model`'s context window (minus a reserved
+/// completion margin). Any leading `system` message is never dropped, and
+/// neither is the newest message, so the result can still exceed the budget.
+///
+/// Returns the final estimated prompt token count.
+pub fn trim_to_budget(model: &str, messages: &mut Vec<Message>) -> usize {
+    let budget = context_window(model).saturating_sub(COMPLETION_MARGIN);
+
+    let protected = if messages.first().is_some_and(|m| m.role == "system") {
+        1
+    } else {
+        0
+    };
+
+    while estimate_tokens(model, messages) > budget && messages.len() > protected + 1 {
+        messages.remove(protected);
+    }
+
+    estimate_tokens(model, messages)
+}
@@ -0,0 +1,103 @@
+/// Discord's hard cap on a single message's character length.
+const DISCORD_MESSAGE_LIMIT: usize = 2000;
+
+/// Clamps `text` to `DISCORD_MESSAGE_LIMIT` *before* a transform runs, so
+/// none of these blow up on pathologically large input.
+fn bounded(text: &str) -> &str {
+    match text.char_indices().nth(DISCORD_MESSAGE_LIMIT) {
+        Some((byte_idx, _)) => &text[..byte_idx],
+        None => text,
+    }
+}
+
+fn truncate(text: String) -> String {
+    if text.chars().count() <= DISCORD_MESSAGE_LIMIT {
+        text
+    } else {
+        text.chars().take(DISCORD_MESSAGE_LIMIT).collect()
+    }
+}
+
+const EMOTICONS: [&str; 4] = ["(・`ω´・)", "owo", "uwu", ">w<"];
+
+/// Swaps `r`/`l` for `w`, stutters every third word, and appends a
+/// deterministically-chosen emoticon.
+pub fn owoify(text: &str) -> String {
+    let text = bounded(text);
+
+    let mut out = String::with_capacity(text.len());
+
+    for (i, word) in text.split(' ').enumerate() {
+        if i > 0 {
+            out.push(' ');
+        }
+
+        let first = word.chars().next();
+
+        if i % 3 == 0 && first.is_some_and(char::is_alphabetic) {
+            out.push(first.unwrap());
+            out.push('-');
+        }
+
+        for ch in word.chars() {
+            match ch {
+                'r' | 'l' => out.push('w'),
+                'R' | 'L' => out.push('W'),
+                other => out.push(other),
+            }
+        }
+    }
+
+    out.push(' ');
+    out.push_str(EMOTICONS[out.len() % EMOTICONS.len()]);
+
+    truncate(out)
+}
+
+/// Substitutes common leetspeak digits for their look-alike letters.
+pub fn leetspeak(text: &str) -> String {
+    let text = bounded(text);
+
+    let out = text
+        .chars()
+        .map(|ch| match ch {
+            'a' | 'A' => '4',
+            'e' | 'E' => '3',
+            'i' | 'I' => '1',
+            'o' | 'O' => '0',
+            's' | 'S' => '5',
+            't' | 'T' => '7',
+            other => other,
+        })
+        .collect();
+
+    truncate(out)
+}
+
+/// Alternates the case of every alphabetic character ("mOcKiNg SpOnGeBoB").
+pub fn mock_case(text: &str) -> String {
+    let text = bounded(text);
+
+    let mut upper = false;
+
+    let out = text
+        .chars()
+        .map(|ch| {
+            if !ch.is_alphabetic() {
+                return ch;
+            }
+
+            let mapped = if upper {
+                ch.to_ascii_uppercase()
+            } else {
+                ch.to_ascii_lowercase()
+            };
+
+            upper = !upper;
+
+            mapped
+        })
+        .collect();
+
+    truncate(out)
+}